@@ -8,19 +8,46 @@ use crossterm::{
 use humansize::{format_size, BINARY};
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
     text::Span,
     widgets::{Block, Borders, List, ListItem, Paragraph},
-    Terminal,
+    Frame, Terminal,
 };
 use std::{
+    cmp::Reverse,
+    collections::HashSet,
     error::Error,
     fs, io,
+    io::Read as _,
     path::{Path, PathBuf},
     process::Command,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, SystemTime},
 };
 
+/// Minimum terminal width (in columns) before we fall back to showing only
+/// the active pane instead of splitting the screen in two.
+const MIN_DUAL_PANE_WIDTH: u16 = 100;
+
+/// Height in rows of the preview panel.
+const PREVIEW_HEIGHT: u16 = 11;
+/// Cap on how many bytes of a file we read for the preview, so a huge file
+/// doesn't stall the UI thread.
+const PREVIEW_MAX_BYTES: usize = 64 * 1024;
+/// Cap on how many lines/hex rows we keep from a preview read.
+const PREVIEW_MAX_ROWS: usize = 500;
+
+/// How many directory levels the `/` fuzzy finder descends while collecting
+/// candidates, so a deep tree can't stall the UI thread.
+const FUZZY_MAX_DEPTH: usize = 8;
+/// Cap on how many candidate paths the fuzzy finder collects, independent of
+/// depth (a shallow but very wide tree is just as dangerous).
+const FUZZY_MAX_CANDIDATES: usize = 5000;
+/// How many top-scoring matches the fuzzy finder keeps on screen.
+const FUZZY_MAX_RESULTS: usize = 200;
+
 /// Interactive file browser
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -42,26 +69,128 @@ struct FileEntry {
     modified: String,
 }
 
-struct App {
+/// One row of a flattened tree listing: a directory entry plus the branch
+/// glyphs (`├─`, `└─`, `│ `) that encode its indentation.
+struct TreeNode {
+    path: PathBuf,
+    name: String,
+    is_dir: bool,
+    branch: String,
+    size: u64,
+    modified: String,
+    expanded: bool,
+}
+
+/// Whether a clipboard of flagged paths should be copied or moved on paste.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ClipKind {
+    Copy,
+    Move,
+}
+
+/// Paths staged by a previous yank/cut, waiting to be pasted into the other pane.
+struct Clipboard {
+    paths: Vec<PathBuf>,
+    kind: ClipKind,
+}
+
+/// Shared state updated by the background copy/move thread and polled by the
+/// render loop to draw the progress bar in the info panel.
+struct CopyProgress {
+    total_bytes: u64,
+    copied_bytes: u64,
+    done: bool,
+    error: Option<String>,
+    /// Source paths successfully removed so far (move only), so the render
+    /// loop can drop them from `Pane::flagged` once the job finishes.
+    moved: Vec<PathBuf>,
+}
+
+/// One independently-browsable directory listing. Dual-pane mode is just two
+/// of these side by side, with `App::active` tracking which one has focus.
+struct Pane {
     current_dir: PathBuf,
     entries: Vec<FileEntry>,
     selected_index: usize,
-    show_hidden: bool,
+    flagged: HashSet<PathBuf>,
+    tree_mode: bool,
+    /// Directories that are expanded in tree mode, keyed by absolute path so
+    /// state survives rebuilding the flattened node list.
+    expanded: HashSet<PathBuf>,
+    tree_nodes: Vec<TreeNode>,
+    /// Path the cached preview below was built from; `None` once nothing is
+    /// selected. Rebuilt only when the selection moves to a different path.
+    preview_path: Option<PathBuf>,
+    preview_lines: Vec<String>,
+    preview_scroll: usize,
+    /// Cheap fingerprint of `current_dir` as of the last `refresh_entries`,
+    /// so the periodic external-change check can skip re-scanning when
+    /// nothing has actually moved.
+    dir_signature: Option<DirSignature>,
 }
 
-impl App {
+impl Pane {
     fn new(path: PathBuf, show_hidden: bool) -> Self {
-        let mut app = App {
+        let mut pane = Pane {
             current_dir: path,
             entries: Vec::new(),
             selected_index: 0,
-            show_hidden,
+            flagged: HashSet::new(),
+            tree_mode: false,
+            expanded: HashSet::new(),
+            tree_nodes: Vec::new(),
+            preview_path: None,
+            preview_lines: Vec::new(),
+            preview_scroll: 0,
+            dir_signature: None,
         };
-        app.refresh_entries();
-        app
+        pane.refresh_entries(show_hidden);
+        pane
+    }
+
+    /// Re-scan `current_dir` only if its signature has changed since the
+    /// last refresh, preserving the selection by name across the rescan.
+    fn refresh_if_changed(&mut self, show_hidden: bool) {
+        if dir_signature(&self.current_dir) == self.dir_signature {
+            return;
+        }
+        let selected_name = self.visible_selected().map(|(_, name, _)| name);
+
+        self.refresh_entries(show_hidden);
+
+        if let Some(name) = selected_name {
+            let index = if self.tree_mode {
+                self.tree_nodes.iter().position(|n| n.name == name)
+            } else {
+                self.entries.iter().position(|e| e.name == name)
+            };
+            if let Some(index) = index {
+                self.selected_index = index;
+            }
+        }
     }
 
-    fn refresh_entries(&mut self) {
+    fn refresh_entries(&mut self, show_hidden: bool) {
+        // Figure out which directory we can actually list before touching
+        // any state. `current_dir` can vanish out from under us at any
+        // time now (deleted, unmounted, permission revoked) since this runs
+        // on every automatic background refresh, not just user-driven
+        // navigation — so on failure we leave the pane showing its last
+        // good listing instead of panicking.
+        let entries = match fs::read_dir(&self.current_dir) {
+            Ok(entries) => entries,
+            Err(_) => match self.current_dir.parent().map(PathBuf::from) {
+                Some(parent) => match fs::read_dir(&parent) {
+                    Ok(entries) => {
+                        self.current_dir = parent;
+                        entries
+                    }
+                    Err(_) => return,
+                },
+                None => return,
+            },
+        };
+
         self.entries.clear();
         self.selected_index = 0;
 
@@ -76,24 +205,13 @@ impl App {
             });
         }
 
-        // Get all entries in the current directory
-        let entries = fs::read_dir(&self.current_dir).unwrap_or_else(|_| {
-            // If we can't read the directory, try to go up one level
-            if let Some(parent) = self.current_dir.parent() {
-                self.current_dir = parent.to_path_buf();
-                fs::read_dir(&self.current_dir).unwrap()
-            } else {
-                panic!("Cannot read directory: {:?}", self.current_dir);
-            }
-        });
-
         // Process each entry
         for entry in entries.filter_map(Result::ok) {
             let path = entry.path();
             let file_name = path.file_name().unwrap().to_string_lossy().to_string();
 
             // Skip hidden files/dirs if show_hidden is false
-            if !self.show_hidden && file_name.starts_with('.') {
+            if !show_hidden && file_name.starts_with('.') {
                 continue;
             }
 
@@ -124,9 +242,15 @@ impl App {
             (false, true) => std::cmp::Ordering::Greater,
             _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
         });
+
+        if self.tree_mode {
+            self.rebuild_tree(show_hidden);
+        }
+
+        self.dir_signature = dir_signature(&self.current_dir);
     }
 
-    fn navigate_to(&mut self, path: &Path) {
+    fn navigate_to(&mut self, path: &Path, show_hidden: bool) {
         if path.is_dir() {
             // Canonicalize the path to resolve any ".." components
             if let Ok(canonical_path) = fs::canonicalize(path) {
@@ -135,11 +259,11 @@ impl App {
                 // Fallback to the original path if canonicalization fails
                 self.current_dir = path.to_path_buf();
             }
-            self.refresh_entries();
+            self.refresh_entries(show_hidden);
         }
     }
 
-    fn navigate_up(&mut self) {
+    fn navigate_up(&mut self, show_hidden: bool) {
         if let Some(parent) = self.current_dir.parent() {
             // Canonicalize the parent path to resolve any ".." components
             if let Ok(canonical_path) = fs::canonicalize(parent) {
@@ -148,14 +272,819 @@ impl App {
                 // Fallback to the original parent path if canonicalization fails
                 self.current_dir = parent.to_path_buf();
             }
-            self.refresh_entries();
+            self.refresh_entries(show_hidden);
+        }
+    }
+
+    /// Rebuild the flattened, depth-first list of visible tree nodes from
+    /// `current_dir`, honoring which directories are in `expanded`.
+    fn rebuild_tree(&mut self, show_hidden: bool) {
+        self.tree_nodes = build_tree_nodes(&self.current_dir, show_hidden, &self.expanded, "");
+        if self.selected_index >= self.tree_nodes.len() {
+            self.selected_index = self.tree_nodes.len().saturating_sub(1);
+        }
+    }
+
+    fn toggle_tree_mode(&mut self, show_hidden: bool) {
+        self.tree_mode = !self.tree_mode;
+        self.selected_index = 0;
+        if self.tree_mode {
+            self.rebuild_tree(show_hidden);
+        }
+    }
+
+    /// Fold/unfold the directory under the cursor in place, rather than
+    /// navigating into it. No-op outside tree mode or on a file.
+    fn toggle_expand_selected(&mut self, show_hidden: bool) {
+        if !self.tree_mode {
+            return;
+        }
+        if let Some(node) = self.tree_nodes.get(self.selected_index) {
+            if node.is_dir {
+                let path = node.path.clone();
+                if !self.expanded.remove(&path) {
+                    self.expanded.insert(path);
+                }
+                self.rebuild_tree(show_hidden);
+            }
+        }
+    }
+
+    /// The path, display name, and dir-ness of whichever row is under the
+    /// cursor, in either flat or tree mode.
+    fn visible_selected(&self) -> Option<(PathBuf, String, bool)> {
+        if self.tree_mode {
+            self.tree_nodes
+                .get(self.selected_index)
+                .map(|n| (n.path.clone(), n.name.clone(), n.is_dir))
+        } else {
+            self.entries
+                .get(self.selected_index)
+                .map(|e| (e.path.clone(), e.name.clone(), e.is_dir))
+        }
+    }
+
+    fn visible_len(&self) -> usize {
+        if self.tree_mode {
+            self.tree_nodes.len()
+        } else {
+            self.entries.len()
+        }
+    }
+
+    fn all_visible_paths(&self) -> Vec<PathBuf> {
+        if self.tree_mode {
+            self.tree_nodes.iter().map(|n| n.path.clone()).collect()
+        } else {
+            self.entries
+                .iter()
+                .filter(|e| e.name != "..")
+                .map(|e| e.path.clone())
+                .collect()
+        }
+    }
+
+    fn toggle_flag_selected(&mut self) {
+        if let Some((path, name, _)) = self.visible_selected() {
+            if name == ".." {
+                return;
+            }
+            if !self.flagged.remove(&path) {
+                self.flagged.insert(path);
+            }
+        }
+    }
+
+    fn flag_all(&mut self) {
+        for path in self.all_visible_paths() {
+            self.flagged.insert(path);
+        }
+    }
+
+    fn clear_flags(&mut self) {
+        self.flagged.clear();
+    }
+
+    /// The flagged set, or the currently highlighted entry when nothing is flagged.
+    fn targets(&self) -> Vec<PathBuf> {
+        if !self.flagged.is_empty() {
+            self.flagged.iter().cloned().collect()
+        } else {
+            self.visible_selected()
+                .filter(|(_, name, _)| name != "..")
+                .map(|(path, _, _)| vec![path])
+                .unwrap_or_default()
+        }
+    }
+
+    /// Recompute the preview only if the selection has moved to a different
+    /// path since the last call; otherwise this is a no-op.
+    fn refresh_preview(&mut self) {
+        let current = self.visible_selected().map(|(path, _, _)| path);
+        if current == self.preview_path {
+            return;
+        }
+        self.preview_scroll = 0;
+        self.preview_lines = match &current {
+            Some(path) => build_preview_lines(path),
+            None => Vec::new(),
+        };
+        self.preview_path = current;
+    }
+
+    fn scroll_preview(&mut self, delta: isize) {
+        let max = self.preview_lines.len().saturating_sub(1) as isize;
+        self.preview_scroll = (self.preview_scroll as isize + delta).clamp(0, max) as usize;
+    }
+
+    fn yank(&mut self, kind: ClipKind) -> Option<Clipboard> {
+        let paths = self.targets();
+        if paths.is_empty() {
+            None
+        } else {
+            Some(Clipboard { paths, kind })
+        }
+    }
+}
+
+/// Input mode for the status-bar prompt, mirroring dirbuilder's `Mode` enum:
+/// outside `Default`, keystrokes edit `App::cmd_buf` instead of driving
+/// navigation.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Default,
+    Renaming,
+    CreatingFile,
+    CreatingDir,
+    RunningCommand,
+    FuzzyFind,
+}
+
+impl Mode {
+    fn prompt(self) -> &'static str {
+        match self {
+            Mode::Default => "",
+            Mode::Renaming => "Rename to: ",
+            Mode::CreatingFile => "New file: ",
+            Mode::CreatingDir => "New directory: ",
+            Mode::RunningCommand => "Command (%s = selected path): ",
+            Mode::FuzzyFind => "/",
+        }
+    }
+}
+
+struct App {
+    panes: [Pane; 2],
+    active: usize,
+    show_hidden: bool,
+    clipboard: Option<Clipboard>,
+    progress: Option<Arc<Mutex<CopyProgress>>>,
+    openers: Openers,
+    mode: Mode,
+    cmd_buf: String,
+    command_output: Option<String>,
+    /// Most-recent-last log of this session's trashed files, so `u` can undo
+    /// the last deletion without opening the trash browser.
+    trash_log: Vec<TrashEntry>,
+    /// Whether the trash browser is showing instead of the normal panes.
+    trash_view: bool,
+    trash_entries: Vec<TrashEntry>,
+    trash_selected: usize,
+    /// Paths collected from `current_dir` when `/` is pressed, re-scored
+    /// against `cmd_buf` on every keystroke.
+    fuzzy_candidates: Vec<PathBuf>,
+    fuzzy_matches: Vec<FuzzyMatch>,
+    fuzzy_selected: usize,
+}
+
+impl App {
+    fn new(path: PathBuf, show_hidden: bool) -> Self {
+        App {
+            panes: [
+                Pane::new(path.clone(), show_hidden),
+                Pane::new(path, show_hidden),
+            ],
+            active: 0,
+            show_hidden,
+            clipboard: None,
+            progress: None,
+            openers: Openers::load(),
+            mode: Mode::Default,
+            cmd_buf: String::new(),
+            command_output: None,
+            trash_log: Vec::new(),
+            trash_view: false,
+            trash_entries: Vec::new(),
+            trash_selected: 0,
+            fuzzy_candidates: Vec::new(),
+            fuzzy_matches: Vec::new(),
+            fuzzy_selected: 0,
+        }
+    }
+
+    /// Move the active pane's targets to the XDG trash, logging each for `u`.
+    fn delete_selected(&mut self, show_hidden: bool) {
+        for path in self.active_pane().targets() {
+            match move_to_trash(&path) {
+                Ok(entry) => {
+                    self.active_pane_mut().flagged.remove(&path);
+                    self.trash_log.push(entry);
+                }
+                Err(err) => {
+                    self.command_output = Some(format!("trash failed for {:?}: {}", path, err));
+                }
+            }
+        }
+        self.active_pane_mut().refresh_entries(show_hidden);
+    }
+
+    /// Restore the most recently trashed entry to where it came from.
+    fn undo_trash(&mut self) {
+        if let Some(entry) = self.trash_log.pop() {
+            match restore_trash_entry(&entry) {
+                Ok(restored) if restored != entry.original_path => {
+                    self.command_output = Some(format!(
+                        "restored to {} ({} now occupied)",
+                        restored.display(),
+                        entry.original_path.display()
+                    ));
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    self.command_output = Some(format!("restore failed: {}", err));
+                }
+            }
+            self.refresh_both();
+        } else {
+            self.command_output = Some(String::from("nothing to undo"));
+        }
+    }
+
+    /// Toggle the trash browser, refreshing its listing when opening.
+    fn toggle_trash_view(&mut self) {
+        self.trash_view = !self.trash_view;
+        if self.trash_view {
+            self.trash_entries = list_trash();
+            self.trash_selected = 0;
+        }
+    }
+
+    /// Restore whichever trash entry is under the cursor in the browser.
+    fn restore_selected_trash_entry(&mut self) {
+        if self.trash_selected >= self.trash_entries.len() {
+            return;
+        }
+        let entry = self.trash_entries.remove(self.trash_selected);
+        match restore_trash_entry(&entry) {
+            Ok(restored) if restored != entry.original_path => {
+                self.command_output = Some(format!(
+                    "restored to {} ({} now occupied)",
+                    restored.display(),
+                    entry.original_path.display()
+                ));
+            }
+            Ok(_) => {}
+            Err(err) => {
+                self.command_output = Some(format!("restore failed: {}", err));
+            }
+        }
+        self.trash_log.retain(|e| e.trashed_path != entry.trashed_path);
+        self.trash_selected = self.trash_selected.min(self.trash_entries.len().saturating_sub(1));
+        self.refresh_both();
+    }
+
+    /// Enter an input mode, pre-filling `cmd_buf` with the selected entry's
+    /// name when renaming.
+    fn enter_mode(&mut self, mode: Mode) {
+        self.cmd_buf = if mode == Mode::Renaming {
+            self.active_pane()
+                .visible_selected()
+                .map(|(_, name, _)| name)
+                .filter(|name| name != "..")
+                .unwrap_or_default()
+        } else {
+            String::new()
+        };
+        self.mode = mode;
+        if mode == Mode::FuzzyFind {
+            self.fuzzy_candidates =
+                collect_fuzzy_candidates(&self.active_pane().current_dir, self.show_hidden);
+            self.update_fuzzy_matches();
+        }
+    }
+
+    /// Re-score `fuzzy_candidates` against `cmd_buf`, keeping the top
+    /// `FUZZY_MAX_RESULTS` by score.
+    fn update_fuzzy_matches(&mut self) {
+        let mut matches: Vec<FuzzyMatch> = self
+            .fuzzy_candidates
+            .iter()
+            .filter_map(|path| {
+                let candidate = path.to_string_lossy();
+                fuzzy_score(&self.cmd_buf, &candidate).map(|score| FuzzyMatch {
+                    path: path.clone(),
+                    score,
+                })
+            })
+            .collect();
+        matches.sort_by_key(|m| Reverse(m.score));
+        matches.truncate(FUZZY_MAX_RESULTS);
+        self.fuzzy_matches = matches;
+        self.fuzzy_selected = 0;
+    }
+
+    /// Navigate the active pane to the directory containing the selected
+    /// match (for files and directories alike) and highlight it there.
+    fn jump_to_fuzzy_match(&mut self, show_hidden: bool) {
+        let Some(m) = self.fuzzy_matches.get(self.fuzzy_selected) else {
+            return;
+        };
+        let target = m.path.clone();
+        // Always jump to the parent and select `target` there, whether it's a
+        // file or a directory — navigating into the target itself would leave
+        // nothing to select (it's not one of its own children).
+        let dir = target
+            .parent()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| target.clone());
+
+        let pane = self.active_pane_mut();
+        pane.tree_mode = false;
+        pane.navigate_to(&dir, show_hidden);
+        if let Some(index) = pane.entries.iter().position(|e| e.path == target) {
+            pane.selected_index = index;
+        }
+    }
+
+    fn cancel_mode(&mut self) {
+        self.mode = Mode::Default;
+        self.cmd_buf.clear();
+        self.fuzzy_candidates.clear();
+        self.fuzzy_matches.clear();
+    }
+
+    /// Run whatever `mode` was prompting for against `cmd_buf`, then return
+    /// to `Mode::Default` and refresh the active pane.
+    fn commit_mode(&mut self, show_hidden: bool) {
+        match self.mode {
+            Mode::Default => {}
+            Mode::Renaming => {
+                if let Some((old_path, name, _)) = self.active_pane().visible_selected() {
+                    if name != ".." && !self.cmd_buf.is_empty() {
+                        let new_path = old_path.with_file_name(&self.cmd_buf);
+                        if let Err(err) = fs::rename(&old_path, &new_path) {
+                            self.command_output = Some(format!("rename failed: {}", err));
+                        }
+                    }
+                }
+            }
+            Mode::CreatingFile => {
+                if !self.cmd_buf.is_empty() {
+                    let path = self.active_pane().current_dir.join(&self.cmd_buf);
+                    if let Err(err) = fs::File::create(&path) {
+                        self.command_output = Some(format!("create failed: {}", err));
+                    }
+                }
+            }
+            Mode::CreatingDir => {
+                if !self.cmd_buf.is_empty() {
+                    let path = self.active_pane().current_dir.join(&self.cmd_buf);
+                    if let Err(err) = fs::create_dir(&path) {
+                        self.command_output = Some(format!("mkdir failed: {}", err));
+                    }
+                }
+            }
+            Mode::RunningCommand => {
+                let target = self.active_pane().visible_selected().map(|(path, _, _)| path);
+                let substituted = match &target {
+                    Some(path) => self.cmd_buf.replace("%s", &path.to_string_lossy()),
+                    None => self.cmd_buf.clone(),
+                };
+                self.command_output = Some(match Command::new("sh").arg("-c").arg(&substituted).output() {
+                    Ok(output) => {
+                        let mut combined = String::from_utf8_lossy(&output.stdout).into_owned();
+                        combined.push_str(&String::from_utf8_lossy(&output.stderr));
+                        if combined.trim().is_empty() {
+                            format!("(no output, {})", output.status)
+                        } else {
+                            combined
+                        }
+                    }
+                    Err(err) => format!("failed to run command: {}", err),
+                });
+            }
+            Mode::FuzzyFind => self.jump_to_fuzzy_match(show_hidden),
+        }
+        let refresh_active_pane = self.mode != Mode::FuzzyFind;
+        self.mode = Mode::Default;
+        self.cmd_buf.clear();
+        self.fuzzy_candidates.clear();
+        self.fuzzy_matches.clear();
+        if refresh_active_pane {
+            self.active_pane_mut().refresh_entries(show_hidden);
         }
     }
 
+    fn active_pane(&self) -> &Pane {
+        &self.panes[self.active]
+    }
+
+    fn active_pane_mut(&mut self) -> &mut Pane {
+        &mut self.panes[self.active]
+    }
+
+    fn inactive_pane(&self) -> &Pane {
+        &self.panes[1 - self.active]
+    }
+
+    fn switch_focus(&mut self) {
+        self.active = 1 - self.active;
+    }
+
     fn toggle_hidden_files(&mut self) {
         self.show_hidden = !self.show_hidden;
-        self.refresh_entries();
+        for pane in &mut self.panes {
+            pane.refresh_entries(self.show_hidden);
+        }
     }
+
+    fn yank(&mut self, kind: ClipKind) {
+        if let Some(clipboard) = self.active_pane_mut().yank(kind) {
+            self.clipboard = Some(clipboard);
+        }
+    }
+
+    /// Kick off a background copy/move of the clipboard contents from the
+    /// active pane into the other pane's directory, tracked through
+    /// `self.progress` so the render loop can draw a progress bar.
+    fn paste(&mut self) {
+        let Some(clipboard) = self.clipboard.take() else {
+            return;
+        };
+        let dest_dir = self.inactive_pane().current_dir.clone();
+
+        // Both panes start on the same directory and nothing stops the user
+        // from navigating them back together, so pasting into the pane the
+        // files already live in would copy/move each entry onto itself —
+        // `fs::copy` truncates the destination before reading, so this would
+        // zero the file out (and then delete it outright on a move).
+        if dest_dir == self.active_pane().current_dir {
+            self.command_output = Some(String::from("cannot paste into the source directory"));
+            self.clipboard = Some(clipboard);
+            return;
+        }
+
+        let total_bytes: u64 = clipboard.paths.iter().map(|p| dir_size(p)).sum();
+
+        let progress = Arc::new(Mutex::new(CopyProgress {
+            total_bytes,
+            copied_bytes: 0,
+            done: false,
+            error: None,
+            moved: Vec::new(),
+        }));
+        self.progress = Some(progress.clone());
+
+        thread::spawn(move || {
+            for src in &clipboard.paths {
+                let Some(name) = src.file_name() else {
+                    continue;
+                };
+                let dest = dest_dir.join(name);
+
+                if dest == *src {
+                    continue;
+                }
+                if dest.starts_with(src) {
+                    progress.lock().unwrap().error = Some(format!(
+                        "cannot paste {} into its own descendant",
+                        src.display()
+                    ));
+                    break;
+                }
+                if dest.exists() {
+                    progress.lock().unwrap().error =
+                        Some(format!("{} already exists", dest.display()));
+                    break;
+                }
+
+                let result = copy_recursive(src, &dest, &progress);
+                if let Err(err) = result {
+                    progress.lock().unwrap().error = Some(err.to_string());
+                    break;
+                }
+                if clipboard.kind == ClipKind::Move {
+                    let removed = if src.is_dir() {
+                        fs::remove_dir_all(src)
+                    } else {
+                        fs::remove_file(src)
+                    };
+                    if let Err(err) = removed {
+                        progress.lock().unwrap().error = Some(err.to_string());
+                        break;
+                    }
+                    progress.lock().unwrap().moved.push(src.clone());
+                }
+            }
+            progress.lock().unwrap().done = true;
+        });
+    }
+
+    fn refresh_both(&mut self) {
+        for pane in &mut self.panes {
+            pane.refresh_entries(self.show_hidden);
+        }
+    }
+
+    /// Called on every event-loop tick: re-scan any pane whose directory has
+    /// changed on disk since the last check.
+    fn refresh_changed_panes(&mut self) {
+        let show_hidden = self.show_hidden;
+        for pane in &mut self.panes {
+            pane.refresh_if_changed(show_hidden);
+        }
+    }
+}
+
+/// Total size in bytes of `path`, recursing into directories. Unreadable entries
+/// are skipped rather than aborting the whole scan.
+fn dir_size(path: &Path) -> u64 {
+    let metadata = match fs::metadata(path) {
+        Ok(meta) => meta,
+        Err(_) => return 0,
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| dir_size(&entry.path()))
+        .sum()
+}
+
+/// Copy `src` to `dst`, recursing into directories and adding each file's byte
+/// count to `progress` as it completes so the UI thread can render a ratio.
+fn copy_recursive(src: &Path, dst: &Path, progress: &Arc<Mutex<CopyProgress>>) -> io::Result<()> {
+    if src.is_dir() {
+        fs::create_dir_all(dst)?;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            let child_dst = dst.join(entry.file_name());
+            copy_recursive(&entry.path(), &child_dst, progress)?;
+        }
+    } else {
+        fs::copy(src, dst)?;
+        let len = fs::metadata(src).map(|m| m.len()).unwrap_or(0);
+        progress.lock().unwrap().copied_bytes += len;
+    }
+    Ok(())
+}
+
+/// Recursively flatten `dir` into depth-first `TreeNode`s, descending into
+/// directories present in `expanded`. `prefix` carries the accumulated
+/// branch glyphs (`│  ` / `   `) down from each ancestor level.
+fn build_tree_nodes(
+    dir: &Path,
+    show_hidden: bool,
+    expanded: &HashSet<PathBuf>,
+    prefix: &str,
+) -> Vec<TreeNode> {
+    let mut out = Vec::new();
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return out;
+    };
+
+    let mut children: Vec<PathBuf> = read_dir
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|path| {
+            show_hidden
+                || !path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().starts_with('.'))
+                    .unwrap_or(false)
+        })
+        .collect();
+
+    children.sort_by(|a, b| match (a.is_dir(), b.is_dir()) {
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        _ => a
+            .file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .cmp(&b.file_name().map(|n| n.to_string_lossy().to_lowercase())),
+    });
+
+    let last = children.len().saturating_sub(1);
+    for (i, path) in children.into_iter().enumerate() {
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        let metadata = fs::metadata(&path).ok();
+        let is_dir = metadata.as_ref().map(|m| m.is_dir()).unwrap_or(false);
+        let size = metadata
+            .as_ref()
+            .map(|m| if is_dir { 0 } else { m.len() })
+            .unwrap_or(0);
+        let modified = metadata
+            .as_ref()
+            .map(format_modified_time)
+            .unwrap_or_default();
+
+        let is_last = i == last;
+        let branch = format!("{}{}", prefix, if is_last { "└─ " } else { "├─ " });
+        let is_expanded = is_dir && expanded.contains(&path);
+
+        out.push(TreeNode {
+            path: path.clone(),
+            name,
+            is_dir,
+            branch,
+            size,
+            modified,
+            expanded: is_expanded,
+        });
+
+        if is_expanded {
+            let child_prefix = format!("{}{}", prefix, if is_last { "   " } else { "│  " });
+            out.extend(build_tree_nodes(&path, show_hidden, expanded, &child_prefix));
+        }
+    }
+
+    out
+}
+
+/// A candidate path scored against the fuzzy finder's query.
+struct FuzzyMatch {
+    path: PathBuf,
+    score: i32,
+}
+
+/// Recursively collect file and directory paths under `root`, bounded by
+/// `FUZZY_MAX_DEPTH` and `FUZZY_MAX_CANDIDATES` so a huge tree can't stall
+/// the `/` picker. Order doesn't matter; every candidate gets re-scored and
+/// re-sorted on each keystroke.
+fn collect_fuzzy_candidates(root: &Path, show_hidden: bool) -> Vec<PathBuf> {
+    // Canonicalize up front so candidate paths agree with the absolute paths
+    // `navigate_to` leaves in `entries` after a jump — otherwise a pane still
+    // sitting on an un-canonicalized `current_dir` (e.g. the default startup
+    // dir `"."`) builds candidates that never match post-navigation entries.
+    let root = fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+    let mut out = Vec::new();
+    collect_fuzzy_candidates_inner(&root, 0, show_hidden, &mut out);
+    out
+}
+
+fn collect_fuzzy_candidates_inner(
+    dir: &Path,
+    depth: usize,
+    show_hidden: bool,
+    out: &mut Vec<PathBuf>,
+) {
+    if depth > FUZZY_MAX_DEPTH || out.len() >= FUZZY_MAX_CANDIDATES {
+        return;
+    }
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in read_dir.filter_map(Result::ok) {
+        if out.len() >= FUZZY_MAX_CANDIDATES {
+            return;
+        }
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !show_hidden && name.starts_with('.') {
+            continue;
+        }
+
+        let is_dir = path.is_dir();
+        out.push(path.clone());
+        if is_dir {
+            collect_fuzzy_candidates_inner(&path, depth + 1, show_hidden, out);
+        }
+    }
+}
+
+/// Score `candidate` as a bonus-weighted subsequence match against `query`,
+/// skim/fzf-style: consecutive matches and matches right after a path or
+/// word boundary score extra, and each skipped character before the next
+/// match costs a small, capped gap penalty. Returns `None` if `query` isn't
+/// a subsequence of `candidate` at all.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let query: Vec<char> = query.to_lowercase().chars().collect();
+    let candidate_chars: Vec<char> = candidate.chars().collect();
+    let candidate_lower: Vec<char> = candidate.to_lowercase().chars().collect();
+
+    let mut score = 0i32;
+    let mut qi = 0;
+    let mut prev_match: Option<usize> = None;
+
+    for (ci, &c) in candidate_lower.iter().enumerate() {
+        if qi >= query.len() {
+            break;
+        }
+        if c != query[qi] {
+            continue;
+        }
+
+        let mut bonus = 1;
+        match prev_match {
+            Some(prev) if ci == prev + 1 => bonus += 8,
+            Some(prev) => score -= ((ci - prev - 1) as i32).min(10),
+            None => {}
+        }
+        let at_boundary = ci == 0
+            || matches!(candidate_chars[ci - 1], '/' | '_' | '-' | '.' | ' ')
+            || (candidate_chars[ci - 1].is_lowercase() && candidate_chars[ci].is_uppercase());
+        if at_boundary {
+            bonus += 6;
+        }
+
+        score += bonus;
+        prev_match = Some(ci);
+        qi += 1;
+    }
+
+    (qi == query.len()).then_some(score)
+}
+
+/// Build the lines shown in the preview panel for `path`: a sorted name
+/// listing for directories, a hex dump for binary files, or the first
+/// `PREVIEW_MAX_ROWS` lines of text otherwise. Reads are capped at
+/// `PREVIEW_MAX_BYTES` so a huge file can't block the UI.
+fn build_preview_lines(path: &Path) -> Vec<String> {
+    let metadata = match fs::metadata(path) {
+        Ok(meta) => meta,
+        Err(err) => return vec![format!("<error reading {:?}: {}>", path, err)],
+    };
+
+    if metadata.is_dir() {
+        return match fs::read_dir(path) {
+            Ok(read_dir) => {
+                let mut names: Vec<String> = read_dir
+                    .filter_map(Result::ok)
+                    .map(|entry| {
+                        let suffix = if entry.path().is_dir() { "/" } else { "" };
+                        format!("{}{}", entry.file_name().to_string_lossy(), suffix)
+                    })
+                    .collect();
+                names.sort();
+                names
+            }
+            Err(err) => vec![format!("<error reading directory: {}>", err)],
+        };
+    }
+
+    let Ok(mut file) = fs::File::open(path) else {
+        return vec![String::from("<unreadable>")];
+    };
+    let mut buf = vec![0u8; PREVIEW_MAX_BYTES];
+    let read = file.read(&mut buf).unwrap_or(0);
+    buf.truncate(read);
+
+    if is_probably_binary(&buf) {
+        hex_dump_lines(&buf)
+    } else {
+        String::from_utf8_lossy(&buf)
+            .lines()
+            .take(PREVIEW_MAX_ROWS)
+            .map(String::from)
+            .collect()
+    }
+}
+
+/// Cheap binary sniff: a NUL byte in the first few KB is a strong signal
+/// the file isn't text.
+fn is_probably_binary(buf: &[u8]) -> bool {
+    buf.iter().take(8192).any(|&b| b == 0)
+}
+
+fn hex_dump_lines(buf: &[u8]) -> Vec<String> {
+    buf.chunks(16)
+        .take(PREVIEW_MAX_ROWS)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let offset = i * 16;
+            let hex: String = chunk.iter().map(|b| format!("{:02x} ", b)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| {
+                    if b.is_ascii_graphic() || b == b' ' {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!("{:08x}  {:<48}{}", offset, hex, ascii)
+        })
+        .collect()
 }
 
 fn format_modified_time(metadata: &fs::Metadata) -> String {
@@ -168,43 +1097,524 @@ fn format_modified_time(metadata: &fs::Metadata) -> String {
         .unwrap_or_else(|_| String::from("Unknown"))
 }
 
-fn open_in_neovim<B: ratatui::backend::Backend + std::io::Write>(
+/// Entry count plus the newest mtime among a directory's entries: cheap to
+/// compute and changes whenever a file is added, removed, or modified,
+/// without diffing the full entry list.
+type DirSignature = (usize, SystemTime);
+
+fn dir_signature(dir: &Path) -> Option<DirSignature> {
+    let read_dir = fs::read_dir(dir).ok()?;
+    let mut count = 0;
+    let mut latest = SystemTime::UNIX_EPOCH;
+    for entry in read_dir.filter_map(Result::ok) {
+        count += 1;
+        if let Ok(modified) = entry.metadata().and_then(|m| m.modified()) {
+            latest = latest.max(modified);
+        }
+    }
+    Some((count, latest))
+}
+
+/// A file moved to the XDG trash: where it now lives under
+/// `~/.local/share/Trash/files`, and the original path recorded in its
+/// sidecar `.trashinfo` so it can be restored later.
+struct TrashEntry {
+    trashed_path: PathBuf,
+    original_path: PathBuf,
+    deleted_at: String,
+}
+
+/// `~/.local/share/Trash`, honoring `$XDG_DATA_HOME` if set.
+fn trash_home() -> Option<PathBuf> {
+    if let Some(data_home) = std::env::var_os("XDG_DATA_HOME") {
+        return Some(PathBuf::from(data_home).join("Trash"));
+    }
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share/Trash"))
+}
+
+/// Ensure `Trash/files` and `Trash/info` exist and return their paths.
+fn ensure_trash_dirs() -> io::Result<(PathBuf, PathBuf)> {
+    let home = trash_home().ok_or_else(|| io::Error::other("no HOME or XDG_DATA_HOME set"))?;
+    let files = home.join("files");
+    let info = home.join("info");
+    fs::create_dir_all(&files)?;
+    fs::create_dir_all(&info)?;
+    Ok((files, info))
+}
+
+/// The first of `path`, `path-1`, `path-2`, ... that doesn't already exist,
+/// so a trash/restore destination never silently clobbers something else.
+fn unique_path(path: PathBuf) -> PathBuf {
+    if !path.exists() {
+        return path;
+    }
+    let mut suffix = 0;
+    loop {
+        suffix += 1;
+        let candidate = PathBuf::from(format!("{}-{}", path.display(), suffix));
+        if !candidate.exists() {
+            return candidate;
+        }
+    }
+}
+
+/// Move `src` to `dst`, falling back to copy-then-remove when they're on
+/// different filesystems (e.g. trashing a file from `/tmp` into
+/// `$XDG_DATA_HOME`), where `fs::rename` can't just relink the inode.
+fn move_path(src: &Path, dst: &Path) -> io::Result<()> {
+    if fs::rename(src, dst).is_ok() {
+        return Ok(());
+    }
+    if src.is_dir() {
+        copy_dir_recursive(src, dst)?;
+        fs::remove_dir_all(src)
+    } else {
+        fs::copy(src, dst)?;
+        fs::remove_file(src)
+    }
+}
+
+/// Plain recursive copy for the `move_path` cross-device fallback; unlike
+/// `copy_recursive` this has no progress bar to report to.
+fn copy_dir_recursive(src: &Path, dst: &Path) -> io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let child_dst = dst.join(entry.file_name());
+        if entry.path().is_dir() {
+            copy_dir_recursive(&entry.path(), &child_dst)?;
+        } else {
+            fs::copy(entry.path(), &child_dst)?;
+        }
+    }
+    Ok(())
+}
+
+/// Move `path` into the XDG trash and write its `.trashinfo` sidecar,
+/// per the freedesktop.org Trash spec. Picks a name clear of collisions by
+/// appending `-1`, `-2`, ... to the trashed file's full name.
+fn move_to_trash(path: &Path) -> io::Result<TrashEntry> {
+    let (files_dir, info_dir) = ensure_trash_dirs()?;
+    let original_path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::other("path has no file name"))?;
+
+    let trashed_path = unique_path(files_dir.join(name));
+    let info_path = info_dir.join(format!(
+        "{}.trashinfo",
+        trashed_path.file_name().unwrap().to_string_lossy()
+    ));
+
+    move_path(path, &trashed_path)?;
+
+    let deleted_at = Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+    fs::write(
+        &info_path,
+        format!(
+            "[Trash Info]\nPath={}\nDeletionDate={}\n",
+            original_path.display(),
+            deleted_at
+        ),
+    )?;
+
+    Ok(TrashEntry {
+        trashed_path,
+        original_path,
+        deleted_at,
+    })
+}
+
+/// Read every `.trashinfo` sidecar back into a `TrashEntry`, for the trash
+/// browser view. Entries whose sidecar or trashed file has gone missing are
+/// skipped rather than erroring the whole listing.
+fn list_trash() -> Vec<TrashEntry> {
+    let Some((files_dir, info_dir)) = ensure_trash_dirs().ok() else {
+        return Vec::new();
+    };
+    let Ok(read_dir) = fs::read_dir(&info_dir) else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<TrashEntry> = read_dir
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let info_path = entry.path();
+            let stem = info_path.file_stem()?.to_string_lossy().to_string();
+            let trashed_path = files_dir.join(&stem);
+            if !trashed_path.exists() {
+                return None;
+            }
+            let contents = fs::read_to_string(&info_path).ok()?;
+            let mut original_path = None;
+            let mut deleted_at = String::new();
+            for line in contents.lines() {
+                if let Some(value) = line.strip_prefix("Path=") {
+                    original_path = Some(PathBuf::from(value));
+                } else if let Some(value) = line.strip_prefix("DeletionDate=") {
+                    deleted_at = value.to_string();
+                }
+            }
+            Some(TrashEntry {
+                trashed_path,
+                original_path: original_path?,
+                deleted_at,
+            })
+        })
+        .collect();
+
+    entries.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+    entries
+}
+
+/// Move a trashed file back to `entry.original_path` and remove its sidecar.
+/// Restore `entry` to its original location, or the first free
+/// `original_path-1`, `original_path-2`, ... if something new now occupies
+/// that path, so an undo can never silently clobber unrelated data.
+fn restore_trash_entry(entry: &TrashEntry) -> io::Result<PathBuf> {
+    if let Some(parent) = entry.original_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let restore_path = unique_path(entry.original_path.clone());
+    move_path(&entry.trashed_path, &restore_path)?;
+
+    let (_, info_dir) = ensure_trash_dirs()?;
+    let info_path = info_dir.join(format!(
+        "{}.trashinfo",
+        entry.trashed_path.file_name().unwrap().to_string_lossy()
+    ));
+    let _ = fs::remove_file(info_path);
+    Ok(restore_path)
+}
+
+/// How to launch a file: the command to run, and whether it needs the
+/// terminal (so we must leave the alternate screen first) or runs detached
+/// in its own window (e.g. a GUI image viewer).
+struct Opener {
+    command: String,
+    needs_terminal: bool,
+}
+
+/// Best-effort text/binary guess for files with no configured opener: peeks
+/// at the first few KB and reuses the same sniff the preview panel does.
+fn looks_like_text(path: &Path) -> bool {
+    let Ok(mut file) = fs::File::open(path) else {
+        return false;
+    };
+    let mut buf = vec![0u8; 8192];
+    let read = file.read(&mut buf).unwrap_or(0);
+    buf.truncate(read);
+    !is_probably_binary(&buf)
+}
+
+/// Extension -> opener mapping, loaded once at startup from
+/// `$HOME/.config/mefiles/openers.conf`. Extensions with no entry fall back
+/// to `$EDITOR` (or `nvim`) for text, `xdg-open` otherwise.
+struct Openers {
+    by_extension: std::collections::HashMap<String, Opener>,
+}
+
+impl Openers {
+    /// Each non-comment line is `extension = command [tty]`; the optional
+    /// trailing `tty` marks a command that needs the terminal suspended
+    /// (an editor or pager) rather than one that opens its own window.
+    fn load() -> Self {
+        let mut by_extension = std::collections::HashMap::new();
+        if let Some(path) = Self::config_path() {
+            if let Ok(contents) = fs::read_to_string(path) {
+                for line in contents.lines() {
+                    let line = line.trim();
+                    if line.is_empty() || line.starts_with('#') {
+                        continue;
+                    }
+                    let Some((ext, rest)) = line.split_once('=') else {
+                        continue;
+                    };
+                    let rest = rest.trim();
+                    let (command, needs_terminal) = match rest
+                        .strip_suffix("tty")
+                        .filter(|command| command.ends_with(char::is_whitespace))
+                    {
+                        Some(command) => (command.trim().to_string(), true),
+                        None => (rest.to_string(), false),
+                    };
+                    by_extension.insert(
+                        ext.trim().to_lowercase(),
+                        Opener {
+                            command,
+                            needs_terminal,
+                        },
+                    );
+                }
+            }
+        }
+        Openers { by_extension }
+    }
+
+    fn config_path() -> Option<PathBuf> {
+        std::env::var_os("HOME")
+            .map(|home| PathBuf::from(home).join(".config/mefiles/openers.conf"))
+    }
+
+    /// The command to launch `path` with, and whether it needs the terminal.
+    fn resolve(&self, path: &Path) -> Opener {
+        if let Some(entry) = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| self.by_extension.get(&ext.to_lowercase()))
+        {
+            return Opener {
+                command: entry.command.clone(),
+                needs_terminal: entry.needs_terminal,
+            };
+        }
+        if looks_like_text(path) {
+            Opener {
+                command: default_editor(),
+                needs_terminal: true,
+            }
+        } else {
+            Opener {
+                command: String::from("xdg-open"),
+                needs_terminal: false,
+            }
+        }
+    }
+}
+
+fn default_editor() -> String {
+    std::env::var("EDITOR").unwrap_or_else(|_| String::from("nvim"))
+}
+
+/// Launch `command` on `path`, suspending the TUI for the duration only when
+/// `needs_terminal` is set (an editor/pager). GUI openers like `xdg-open`
+/// are left to run against the normal screen.
+fn open_path<B: ratatui::backend::Backend + std::io::Write>(
     path: &Path,
     terminal: &mut Terminal<B>,
+    command: &str,
+    needs_terminal: bool,
 ) -> io::Result<()> {
-    // Restore terminal to normal state before launching neovim
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    if needs_terminal {
+        disable_raw_mode()?;
+        execute!(
+            terminal.backend_mut(),
+            LeaveAlternateScreen,
+            DisableMouseCapture
+        )?;
+        terminal.show_cursor()?;
+    }
 
-    // Launch neovim with the selected file
-    let status = Command::new("nvim")
-        .arg(path)
-        .status()
-        .expect("Failed to execute neovim");
+    match Command::new(command).arg(path).status() {
+        Ok(status) if !status.success() => {
+            eprintln!("{} exited with error: {}", command, status)
+        }
+        Err(err) => eprintln!("Failed to execute {}: {}", command, err),
+        Ok(_) => {}
+    }
 
-    // Check if neovim exited successfully
-    if !status.success() {
-        eprintln!("Neovim exited with error: {}", status);
+    if needs_terminal {
+        enable_raw_mode()?;
+        execute!(
+            terminal.backend_mut(),
+            EnterAlternateScreen,
+            EnableMouseCapture
+        )?;
+        terminal.hide_cursor()?;
+        terminal.clear()?;
     }
 
-    // Restore terminal to app state
-    enable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        EnterAlternateScreen,
-        EnableMouseCapture
-    )?;
-    terminal.hide_cursor()?;
+    Ok(())
+}
 
-    // Force a terminal refresh
-    terminal.clear()?;
+/// Render a single pane's file list into `area`, highlighting flagged entries
+/// and coloring the border to show whether the pane has focus.
+fn render_pane(f: &mut Frame, area: Rect, pane: &Pane, is_active: bool) {
+    let items: Vec<ListItem> = if pane.tree_mode {
+        pane.tree_nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| {
+                let marker = if pane.flagged.contains(&node.path) {
+                    "*"
+                } else {
+                    " "
+                };
+                let icon = if node.is_dir {
+                    if node.expanded {
+                        "📂"
+                    } else {
+                        "📁"
+                    }
+                } else {
+                    "📄"
+                };
+                let name = format!(
+                    "{}{}{} {}{}",
+                    marker,
+                    node.branch,
+                    icon,
+                    node.name,
+                    if node.is_dir { "/" } else { "" }
+                );
 
-    Ok(())
+                let size = if node.is_dir {
+                    String::from("DIR")
+                } else {
+                    format_size(node.size, BINARY)
+                };
+
+                let content = format!("{:<40} {:<12} {}", name, size, node.modified);
+
+                let style = if is_active && i == pane.selected_index {
+                    Style::default().fg(Color::Black).bg(Color::White)
+                } else if pane.flagged.contains(&node.path) {
+                    Style::default().fg(Color::Yellow)
+                } else if node.is_dir {
+                    Style::default().fg(Color::Blue)
+                } else {
+                    Style::default()
+                };
+
+                ListItem::new(Span::styled(content, style))
+            })
+            .collect()
+    } else {
+        pane.entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let marker = if pane.flagged.contains(&entry.path) {
+                    "*"
+                } else {
+                    " "
+                };
+                let name = if entry.is_dir {
+                    format!("{}📁 {}/", marker, entry.name)
+                } else {
+                    format!("{}📄 {}", marker, entry.name)
+                };
+
+                let size = if entry.is_dir {
+                    String::from("DIR")
+                } else {
+                    format_size(entry.size, BINARY)
+                };
+
+                let content = format!("{:<30} {:<12} {}", name, size, entry.modified);
+
+                let style = if is_active && i == pane.selected_index {
+                    Style::default().fg(Color::Black).bg(Color::White)
+                } else if pane.flagged.contains(&entry.path) {
+                    Style::default().fg(Color::Yellow)
+                } else if entry.is_dir {
+                    Style::default().fg(Color::Blue)
+                } else {
+                    Style::default()
+                };
+
+                ListItem::new(Span::styled(content, style))
+            })
+            .collect()
+    };
+
+    let title = format!(
+        " {}{} ",
+        pane.current_dir.display(),
+        if pane.flagged.is_empty() {
+            String::new()
+        } else {
+            format!(" [{} flagged]", pane.flagged.len())
+        }
+    );
+    let border_style = if is_active {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default()
+    };
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .title(title),
+    );
+    f.render_widget(list, area);
+}
+
+/// Render the preview panel for whatever the active pane has under its
+/// cursor, applying the pane's current scroll offset.
+fn render_preview(f: &mut Frame, area: Rect, pane: &Pane) {
+    let title = match &pane.preview_path {
+        Some(path) => format!(
+            " Preview: {} ",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        ),
+        None => String::from(" Preview "),
+    };
+
+    let visible_rows = area.height.saturating_sub(2) as usize;
+    let text = pane
+        .preview_lines
+        .iter()
+        .skip(pane.preview_scroll)
+        .take(visible_rows.max(1))
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let preview = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(preview, area);
+}
+
+/// Trash browser: one row per trashed file, showing where it was deleted
+/// from and when, with the cursor row highlighted like a pane listing.
+fn render_trash_view(f: &mut Frame, area: Rect, entries: &[TrashEntry], selected: usize) {
+    let items: Vec<ListItem> = entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let content = format!(
+                "{:<50} {}",
+                entry.original_path.display(),
+                entry.deleted_at
+            );
+            let style = if i == selected {
+                Style::default().fg(Color::Black).bg(Color::White)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Span::styled(content, style))
+        })
+        .collect();
+
+    let title = if entries.is_empty() {
+        " Trash (empty) "
+    } else {
+        " Trash — Enter: restore, T/Esc: close "
+    };
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(list, area);
+}
+
+/// Fuzzy finder results: top matches for the in-progress query, best score
+/// first, with the cursor row highlighted like a pane listing.
+fn render_fuzzy_view(f: &mut Frame, area: Rect, matches: &[FuzzyMatch], selected: usize) {
+    let items: Vec<ListItem> = matches
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let style = if i == selected {
+                Style::default().fg(Color::Black).bg(Color::White)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Span::styled(m.path.to_string_lossy().into_owned(), style))
+        })
+        .collect();
+
+    let title = format!(" Jump to file ({} matches) — Enter: jump, Esc: close ", matches.len());
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(list, area);
 }
 
 fn run_app<B: ratatui::backend::Backend + std::io::Write>(
@@ -212,97 +1622,229 @@ fn run_app<B: ratatui::backend::Backend + std::io::Write>(
     mut app: App,
 ) -> io::Result<()> {
     loop {
+        // Once a background copy/move finishes, drop it so the info panel goes
+        // back to showing the help text instead of a stale progress bar. If it
+        // hit an I/O error partway through, surface that instead of silently
+        // reporting success.
+        if let Some(progress) = &app.progress {
+            let finished = progress.lock().unwrap();
+            if finished.done {
+                let error = finished.error.clone();
+                let moved = finished.moved.clone();
+                drop(finished);
+                for pane in &mut app.panes {
+                    for path in &moved {
+                        pane.flagged.remove(path);
+                    }
+                }
+                app.refresh_both();
+                app.progress = None;
+                if let Some(error) = error {
+                    app.command_output = Some(format!("copy/move failed: {}", error));
+                }
+            }
+        }
+
+        // Pick up external edits: re-scan any pane whose directory's
+        // signature has moved since we last read it.
+        app.refresh_changed_panes();
+
+        // Recompute the preview only when the active pane's selection has
+        // actually moved to a different path.
+        app.active_pane_mut().refresh_preview();
+
         terminal.draw(|f| {
-            let chunks = Layout::default()
+            let outer = Layout::default()
                 .direction(Direction::Vertical)
                 .margin(1)
                 .constraints([
-                    Constraint::Length(1), // Status bar
-                    Constraint::Min(0),    // File list
-                    Constraint::Length(3), // Info panel
+                    Constraint::Length(1),            // Status bar
+                    Constraint::Min(0),                // File list(s)
+                    Constraint::Length(PREVIEW_HEIGHT), // Preview panel
+                    Constraint::Length(3),             // Info panel
                 ])
                 .split(f.area());
 
-            // Status bar
-            let status = format!(" Current directory: {} ", app.current_dir.display());
+            // Status bar: the prompt line takes over while a Mode is active.
+            let status = if app.mode == Mode::Default {
+                format!(
+                    " Pane {}/2: {} ",
+                    app.active + 1,
+                    app.active_pane().current_dir.display()
+                )
+            } else {
+                format!(" {}{}", app.mode.prompt(), app.cmd_buf)
+            };
             let status_bar =
                 Paragraph::new(status).style(Style::default().bg(Color::Blue).fg(Color::White));
-            f.render_widget(status_bar, chunks[0]);
-
-            // File list
-            let items: Vec<ListItem> = app
-                .entries
-                .iter()
-                .enumerate()
-                .map(|(i, entry)| {
-                    let name = if entry.is_dir {
-                        format!("📁 {}/", entry.name)
-                    } else {
-                        format!("📄 {}", entry.name)
-                    };
+            f.render_widget(status_bar, outer[0]);
 
-                    let size = if entry.is_dir {
-                        String::from("DIR")
-                    } else {
-                        format_size(entry.size, BINARY)
-                    };
+            if app.mode == Mode::FuzzyFind {
+                render_fuzzy_view(f, outer[1], &app.fuzzy_matches, app.fuzzy_selected);
+            } else if app.trash_view {
+                render_trash_view(f, outer[1], &app.trash_entries, app.trash_selected);
+            } else if outer[1].width < MIN_DUAL_PANE_WIDTH {
+                // Narrow terminals fall back to showing only the active pane.
+                render_pane(f, outer[1], app.active_pane(), true);
+            } else {
+                let panes = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(outer[1]);
+                render_pane(f, panes[0], &app.panes[0], app.active == 0);
+                render_pane(f, panes[1], &app.panes[1], app.active == 1);
+            }
 
-                    let content = format!("{:<40} {:<12} {}", name, size, entry.modified);
+            render_preview(f, outer[2], app.active_pane());
 
-                    let style = if i == app.selected_index {
-                        Style::default().fg(Color::Black).bg(Color::White)
-                    } else if entry.is_dir {
-                        Style::default().fg(Color::Blue)
-                    } else {
-                        Style::default()
-                    };
-
-                    ListItem::new(Span::styled(content, style))
-                })
-                .collect();
+            // Info panel: a progress bar while a copy/move is in flight, otherwise help text.
+            let info = if let Some(progress) = &app.progress {
+                let progress = progress.lock().unwrap();
+                let ratio = if progress.total_bytes == 0 {
+                    1.0
+                } else {
+                    progress.copied_bytes as f64 / progress.total_bytes as f64
+                };
+                let width = 40;
+                let filled = ((ratio * width as f64) as usize).min(width);
+                let bar = format!(
+                    "[{}{}] {}/{}",
+                    "#".repeat(filled),
+                    "-".repeat(width - filled),
+                    format_size(progress.copied_bytes, BINARY),
+                    format_size(progress.total_bytes, BINARY),
+                );
+                Paragraph::new(bar).block(Block::default().borders(Borders::ALL).title("Progress"))
+            } else if let Some(output) = &app.command_output {
+                Paragraph::new(output.as_str())
+                    .block(Block::default().borders(Borders::ALL).title("Command output"))
+            } else {
+                let help_text = "Tab: Switch pane  ↑/↓: Navigate  PgUp/PgDn: Scroll preview  Enter: Open  o: Open in editor  t: Tree  z: Fold  Space: Flag  a/A: Flag all/clear  y/m: Yank/cut  p: Paste to other pane  d: Trash  u: Undo trash  T: Trash browser  r: Rename  n/N: New file/dir  :: Run command  /: Jump to file  h: Hidden  q: Quit";
+                Paragraph::new(help_text).block(Block::default().borders(Borders::ALL).title("Help"))
+            };
+            f.render_widget(info, outer[3]);
+        })?;
 
-            let files_list =
-                List::new(items).block(Block::default().borders(Borders::ALL).title("Files"));
-            f.render_widget(files_list, chunks[1]);
+        // Poll with a timeout rather than blocking so the progress bar keeps
+        // redrawing while a background copy/move is running.
+        if event::poll(Duration::from_millis(100))? {
+            if let Event::Key(key) = event::read()? {
+                let show_hidden = app.show_hidden;
 
-            // Info panel
-            let help_text =
-                "↑/↓: Navigate  Enter: Open dir/file  Backspace: Up  h: Toggle hidden  q: Quit";
-            let help = Paragraph::new(help_text)
-                .block(Block::default().borders(Borders::ALL).title("Help"));
-            f.render_widget(help, chunks[2]);
-        })?;
+                if app.mode == Mode::FuzzyFind {
+                    match key.code {
+                        KeyCode::Enter => app.commit_mode(show_hidden),
+                        KeyCode::Esc => app.cancel_mode(),
+                        KeyCode::Backspace => {
+                            app.cmd_buf.pop();
+                            app.update_fuzzy_matches();
+                        }
+                        KeyCode::Char(c) => {
+                            app.cmd_buf.push(c);
+                            app.update_fuzzy_matches();
+                        }
+                        KeyCode::Up => app.fuzzy_selected = app.fuzzy_selected.saturating_sub(1),
+                        KeyCode::Down => {
+                            app.fuzzy_selected = (app.fuzzy_selected + 1)
+                                .min(app.fuzzy_matches.len().saturating_sub(1));
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
 
-        if let Event::Key(key) = event::read()? {
-            match key.code {
-                KeyCode::Char('q') => return Ok(()),
-                KeyCode::Char('h') => app.toggle_hidden_files(),
-                KeyCode::Up => {
-                    if app.selected_index > 0 {
-                        app.selected_index -= 1;
+                if app.mode != Mode::Default {
+                    match key.code {
+                        KeyCode::Enter => app.commit_mode(show_hidden),
+                        KeyCode::Esc => app.cancel_mode(),
+                        KeyCode::Backspace => {
+                            app.cmd_buf.pop();
+                        }
+                        KeyCode::Char(c) => app.cmd_buf.push(c),
+                        _ => {}
                     }
+                    continue;
                 }
-                KeyCode::Down => {
-                    if app.selected_index < app.entries.len().saturating_sub(1) {
-                        app.selected_index += 1;
+
+                if app.trash_view {
+                    match key.code {
+                        KeyCode::Char('T') | KeyCode::Esc => app.toggle_trash_view(),
+                        KeyCode::Enter => app.restore_selected_trash_entry(),
+                        KeyCode::Up => app.trash_selected = app.trash_selected.saturating_sub(1),
+                        KeyCode::Down => {
+                            app.trash_selected = (app.trash_selected + 1)
+                                .min(app.trash_entries.len().saturating_sub(1));
+                        }
+                        _ => {}
                     }
+                    continue;
                 }
-                KeyCode::Enter => {
-                    if app.selected_index < app.entries.len() {
-                        let is_dir = app.entries[app.selected_index].is_dir;
-                        let path = app.entries[app.selected_index].path.clone();
-
-                        if is_dir {
-                            // Navigate to directory
-                            app.navigate_to(&path);
-                        } else {
-                            // Open file in neovim
-                            open_in_neovim(&path, terminal)?;
+
+                app.command_output = None;
+                match key.code {
+                    KeyCode::Char('q') => return Ok(()),
+                    KeyCode::Char('h') => app.toggle_hidden_files(),
+                    KeyCode::Tab => app.switch_focus(),
+                    KeyCode::Char(' ') => app.active_pane_mut().toggle_flag_selected(),
+                    KeyCode::Char('a') => app.active_pane_mut().flag_all(),
+                    KeyCode::Char('A') => app.active_pane_mut().clear_flags(),
+                    KeyCode::Char('y') => app.yank(ClipKind::Copy),
+                    KeyCode::Char('m') => app.yank(ClipKind::Move),
+                    KeyCode::Char('p') => app.paste(),
+                    KeyCode::Char('d') => app.delete_selected(show_hidden),
+                    KeyCode::Char('u') => app.undo_trash(),
+                    KeyCode::Char('T') => app.toggle_trash_view(),
+                    KeyCode::Char('t') => app.active_pane_mut().toggle_tree_mode(show_hidden),
+                    KeyCode::Char('z') => app.active_pane_mut().toggle_expand_selected(show_hidden),
+                    KeyCode::Char('r') => app.enter_mode(Mode::Renaming),
+                    KeyCode::Char('n') => app.enter_mode(Mode::CreatingFile),
+                    KeyCode::Char('N') => app.enter_mode(Mode::CreatingDir),
+                    KeyCode::Char(':') => app.enter_mode(Mode::RunningCommand),
+                    KeyCode::Char('/') => app.enter_mode(Mode::FuzzyFind),
+                    KeyCode::Char('o') => {
+                        if let Some((path, _, is_dir)) = app.active_pane().visible_selected() {
+                            if !is_dir {
+                                open_path(&path, terminal, &default_editor(), true)?;
+                            }
+                        }
+                    }
+                    KeyCode::PageUp => app
+                        .active_pane_mut()
+                        .scroll_preview(-(PREVIEW_HEIGHT as isize)),
+                    KeyCode::PageDown => app
+                        .active_pane_mut()
+                        .scroll_preview(PREVIEW_HEIGHT as isize),
+                    KeyCode::Up => {
+                        let pane = app.active_pane_mut();
+                        if pane.selected_index > 0 {
+                            pane.selected_index -= 1;
+                        }
+                    }
+                    KeyCode::Down => {
+                        let pane = app.active_pane_mut();
+                        if pane.selected_index < pane.visible_len().saturating_sub(1) {
+                            pane.selected_index += 1;
+                        }
+                    }
+                    KeyCode::Enter => {
+                        let pane = app.active_pane_mut();
+                        let tree_mode = pane.tree_mode;
+                        if let Some((path, _, is_dir)) = pane.visible_selected() {
+                            if is_dir {
+                                if tree_mode {
+                                    app.active_pane_mut().toggle_expand_selected(show_hidden);
+                                } else {
+                                    app.active_pane_mut().navigate_to(&path, show_hidden);
+                                }
+                            } else {
+                                let opener = app.openers.resolve(&path);
+                                open_path(&path, terminal, &opener.command, opener.needs_terminal)?;
+                            }
                         }
                     }
+                    KeyCode::Backspace => app.active_pane_mut().navigate_up(show_hidden),
+                    _ => {}
                 }
-                KeyCode::Backspace => app.navigate_up(),
-                _ => {}
             }
         }
     }